@@ -1,39 +1,262 @@
-use std::cell::Cell;
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::OnceLock;
 
 use crate::{Elems, GetHash, Hash, Height, Three};
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+/// How long the witness (authentication path) of a particular inserted item should be retained.
+///
+/// Modeled on the `EPHEMERAL`/`CHECKPOINT`/`MARKED` retention classes in Zcash's `shardtree`, this
+/// is attached to each item at insertion time and propagated up through every ancestor segment, so
+/// that the carry path in [`Active::insert`] can decide, subtree by subtree, whether to retain
+/// enough structure to produce a witness or collapse straight to a [`Hash`].
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub(crate) enum Retention {
+    /// This item's witness is never needed, so its subtree may be pruned to a bare [`Hash`] as
+    /// soon as nothing else still requires the structure to compute an ancestor's hash.
+    #[default]
+    Ephemeral,
+    /// This item's witness should be retained until every one of these checkpoint ids has been
+    /// rewound past or evicted -- a combined tag can name more than one id, since a single
+    /// completed subtree can hold leaves belonging to several checkpoints at once.
+    Checkpoint(BTreeSet<u64>),
+    /// This item has been explicitly marked for long-term witnessing (e.g. because the wallet
+    /// owns the note it commits to), and its witness must be retained until it is unmarked.
+    Marked,
+}
+
+impl Retention {
+    /// Tag an item's witness as retained until checkpoint `id` is rewound past or evicted.
+    pub(crate) fn checkpoint(id: u64) -> Self {
+        Retention::Checkpoint(BTreeSet::from([id]))
+    }
+
+    /// Whether a subtree carrying this retention must keep enough structure to produce a witness,
+    /// as opposed to being safely collapsed to a single [`Hash`].
+    pub(crate) fn is_retained(&self) -> bool {
+        !matches!(self, Retention::Ephemeral)
+    }
+
+    /// The stronger of two retention requirements: `Marked` beats `Checkpoint` beats `Ephemeral`.
+    ///
+    /// Used to fold the retention of every item within a subtree into the single retention that
+    /// subtree's carry is tagged with as it propagates up toward the root. Combining two
+    /// `Checkpoint` tags unions their ids, since the resulting subtree holds leaves from both.
+    pub(crate) fn max(self, other: Self) -> Self {
+        use Retention::*;
+        match (self, other) {
+            (Marked, _) | (_, Marked) => Marked,
+            (Checkpoint(mut ids), Checkpoint(other_ids)) => {
+                ids.extend(other_ids);
+                Checkpoint(ids)
+            }
+            (Checkpoint(ids), Ephemeral) | (Ephemeral, Checkpoint(ids)) => Checkpoint(ids),
+            (Ephemeral, Ephemeral) => Ephemeral,
+        }
+    }
+
+    /// Forget `id`, so that it no longer keeps a `Checkpoint`-tagged subtree retained. Once the
+    /// last outstanding id is forgotten this way, the tag becomes `Ephemeral` and the subtree it
+    /// tags is eligible to be collapsed to a bare [`Hash`]. Has no effect on `Marked`.
+    pub(crate) fn retire(&mut self, id: u64) {
+        if let Retention::Checkpoint(ids) = self {
+            ids.remove(&id);
+            if ids.is_empty() {
+                *self = Retention::Ephemeral;
+            }
+        }
+    }
+
+    /// Whether retiring `id` could possibly change anything about the subtree this tags: `false`
+    /// means `retire_slot` can skip walking (and potentially reallocating) that subtree entirely.
+    /// `Marked` conservatively answers `true`, since it doesn't track which checkpoint ids might
+    /// still apply to descendants further down.
+    pub(crate) fn contains(&self, id: u64) -> bool {
+        match self {
+            Retention::Ephemeral => false,
+            Retention::Checkpoint(ids) => ids.contains(&id),
+            Retention::Marked => true,
+        }
+    }
+}
+
+/// The tagged slots making up one level of a tree (a [`Complete`](super::Complete)'s children, or
+/// an [`Active`]'s siblings): retire a checkpoint id from each independently, without collapsing
+/// the level itself.
+///
+/// If none of `siblings` names `id` at all, this returns the original `Three` untouched: no slot
+/// clones or `Three` is rebuilt for an eviction that can't affect anything at this level.
+pub(crate) fn retire_each<Child: GetHash + Height + Retire + Clone>(
+    siblings: Three<(Retention, Result<Child, Hash>)>,
+    id: u64,
+) -> Three<(Retention, Result<Child, Hash>)> {
+    if !any_slot_three(&siblings, |retention| retention.contains(id)) {
+        return siblings;
+    }
+    match siblings.elems() {
+        Elems::_0([]) => Three::new(),
+        Elems::_1([s0]) => push_all([retire_slot(s0.clone(), id)]),
+        Elems::_2([s0, s1]) => push_all([retire_slot(s0.clone(), id), retire_slot(s1.clone(), id)]),
+        Elems::_3([s0, s1, s2]) => push_all([
+            retire_slot(s0.clone(), id),
+            retire_slot(s1.clone(), id),
+            retire_slot(s2.clone(), id),
+        ]),
+    }
+}
+
+/// Retire `id` from a single tagged slot, collapsing it to a bare [`Hash`] if that was the last
+/// reason it was being kept around, and otherwise recursing into it to retire `id` from whatever
+/// it itself retains.
+pub(crate) fn retire_slot<Child: GetHash + Height + Retire>(
+    (mut retention, result): (Retention, Result<Child, Hash>),
+    id: u64,
+) -> (Retention, Result<Child, Hash>) {
+    // `retire_each`'s level-wide short-circuit above only guarantees that *some* slot at this
+    // level names `id`, not that this particular one does -- and a slot reached via
+    // `Complete::retire`'s `Children::Whole` branch skips that check entirely, since it isn't
+    // backed by a `Three` at all. Either way, this slot's own tag is what decides whether there's
+    // anything here for `id` to change.
+    if !retention.contains(id) {
+        return (retention, result);
+    }
+    retention.retire(id);
+    let result = if retention.is_retained() {
+        match result {
+            Ok(child) => child.retire(id),
+            Err(hash) => Err(hash),
+        }
+    } else {
+        Err(match result {
+            Ok(child) => child.hash(),
+            Err(hash) => hash,
+        })
+    };
+    (retention, result)
+}
+
+/// Build a [`Three`] from a fixed-size array of already-prepared items.
+pub(crate) fn push_all<T, const N: usize>(items: [T; N]) -> Three<T> {
+    let mut three = Three::new();
+    for item in items {
+        three = three
+            .push(item)
+            .unwrap_or_else(|_| unreachable!("pushing at most 3 items never overflows a `Three`"));
+    }
+    three
+}
+
+/// Whether any of a set of tagged children still needs to be retained.
+pub(crate) fn any_retained<Child>(children: &[(Retention, Result<Child, Hash>)]) -> bool {
+    children
+        .iter()
+        .any(|(retention, _)| retention.is_retained())
+}
+
+/// Whether any of the (up to three) tagged entries currently held in a [`Three`] still needs to be
+/// retained.
+pub(crate) fn any_retained_three<Child>(
+    siblings: &Three<(Retention, Result<Child, Hash>)>,
+) -> bool {
+    any_slot_three(siblings, Retention::is_retained)
+}
+
+/// Whether any of the (up to three) tagged entries currently held in a [`Three`] satisfies
+/// `predicate`, e.g. [`Retention::is_retained`] or "names this checkpoint id".
+fn any_slot_three<Child>(
+    siblings: &Three<(Retention, Result<Child, Hash>)>,
+    predicate: impl Fn(&Retention) -> bool,
+) -> bool {
+    match siblings.elems() {
+        Elems::_0([]) => false,
+        Elems::_1([s0]) => predicate(&s0.0),
+        Elems::_2([s0, s1]) => predicate(&s0.0) || predicate(&s1.0),
+        Elems::_3([s0, s1, s2]) => predicate(&s0.0) || predicate(&s1.0) || predicate(&s2.0),
+    }
+}
+
+/// Forget a since-evicted checkpoint, collapsing to a bare [`Hash`] whatever subtree was retained
+/// only for it, and potentially collapsing entirely if nothing else retains this node at all.
+///
+/// Implemented by [`super::Complete`], and recursively required of whatever sits at the bottom of
+/// the tree, mirroring [`GetHash`] and [`Witness`].
+pub(crate) trait Retire: Sized {
+    fn retire(self, id: u64) -> Result<Self, Hash>;
+}
+
+/// Like [`Retire`], but for a node that can never itself collapse to a bare [`Hash`] -- the
+/// still-growing frontier of an [`Active`] tree, which by definition always has more insertions
+/// ahead of it. Implemented by [`Active`], and recursively required of whatever sits at the bottom
+/// of the tree.
+pub(crate) trait RetireFrontier {
+    fn retire(&mut self, id: u64);
+}
+
+#[derive(Debug, Clone)]
 pub(crate) struct Active<Focus: crate::Active> {
     focus: Focus,
-    siblings: Three<Result<Focus::Complete, Hash>>,
-    // TODO: replace this with space-saving `Cell<OptionHash>`?
-    hash: Cell<Option<Hash>>,
+    // The retention of everything currently accumulated in `focus`, i.e. not yet completed into a
+    // tagged entry of `siblings`.
+    retention: Retention,
+    siblings: Three<(Retention, Result<Focus::Complete, Hash>)>,
+    // Uses `OnceLock` rather than `Cell<Option<Hash>>` so that `Active` stays `Sync` and can be
+    // shared behind an `Arc` among concurrent readers (e.g. RPC handlers); unlike a hand-rolled
+    // atomic encoding, this doesn't need to know anything about `Hash`'s bit representation.
+    hash: OnceLock<Hash>,
 }
 
 impl<Focus: crate::Active> Active<Focus> {
-    pub(crate) fn from_parts(siblings: Three<Result<Focus::Complete, Hash>>, focus: Focus) -> Self
+    pub(crate) fn from_parts(
+        siblings: Three<(Retention, Result<Focus::Complete, Hash>)>,
+        focus: Focus,
+        retention: Retention,
+    ) -> Self
     where
         Focus: crate::Active + GetHash,
     {
         Self {
-            hash: Cell::new(None),
+            hash: OnceLock::new(),
             siblings,
             focus,
+            retention,
         }
     }
 }
 
+impl<Focus> PartialEq for Active<Focus>
+where
+    Focus: crate::Active + PartialEq,
+    Focus::Complete: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // The memoized hash is populated opportunistically, so two structurally identical
+        // frontiers can have different cache states; compare only the real fields.
+        self.focus == other.focus
+            && self.retention == other.retention
+            && self.siblings == other.siblings
+    }
+}
+
+impl<Focus> Eq for Active<Focus>
+where
+    Focus: crate::Active + Eq,
+    Focus::Complete: Eq,
+{
+}
+
 fn hash_active<Focus: crate::Active + GetHash>(
-    siblings: &Three<Result<Focus::Complete, Hash>>,
+    siblings: &Three<(Retention, Result<Focus::Complete, Hash>)>,
     focus: &Focus,
 ) -> Hash {
     // Get the correct padding hash for this height
     let padding = Hash::padding();
 
-    // Get the hashes of all the `Result<T, Hash>` in the array, hashing `T` as necessary
+    // Get the hashes of all the `Result<T, Hash>` in the array, hashing `T` as necessary; the
+    // retention tag alongside each is irrelevant to the hash itself
     #[inline]
-    fn hashes_of_all<T: GetHash, const N: usize>(full: [&Result<T, Hash>; N]) -> [Hash; N] {
-        full.map(|result| {
+    fn hashes_of_all<T: GetHash, const N: usize>(
+        full: [&(Retention, Result<T, Hash>); N],
+    ) -> [Hash; N] {
+        full.map(|(_retention, result)| {
             result
                 .as_ref()
                 .map(|complete| complete.hash())
@@ -83,14 +306,10 @@ where
 
 impl<Focus: crate::Active> GetHash for Active<Focus> {
     fn hash(&self) -> Hash {
-        match self.hash.get() {
-            Some(hash) => hash,
-            None => {
-                let hash = hash_active(&self.siblings, &self.focus);
-                self.hash.set(Some(hash));
-                hash
-            }
-        }
+        // `get_or_init` blocks concurrent callers rather than racing, so this is computed once.
+        *self
+            .hash
+            .get_or_init(|| hash_active(&self.siblings, &self.focus))
     }
 }
 
@@ -105,33 +324,51 @@ where
     fn singleton(item: Self::Item) -> Self {
         let focus = Focus::singleton(item);
         let siblings = Three::new();
-        Self::from_parts(siblings, focus)
+        Self::from_parts(siblings, focus, Retention::default())
     }
 
     #[inline]
     fn complete(self) -> Result<Self::Complete, Hash> {
-        super::Complete::from_siblings_and_focus_or_else_hash(self.siblings, self.focus.complete())
+        super::Complete::from_siblings_and_focus_or_else_hash(
+            self.siblings,
+            self.retention,
+            self.focus.complete(),
+        )
     }
 
     #[inline]
     fn alter<T>(&mut self, f: impl FnOnce(&mut Self::Item) -> T) -> Option<T> {
         let result = self.focus.alter(f);
-        self.hash.set(None); // the hash could have changed, so clear the cache
+        self.hash.take(); // the hash could have changed, so clear the cache
         result
     }
 
     #[inline]
-    fn insert(self, item: Self::Item) -> Result<Self, (Self::Item, Result<Self::Complete, Hash>)> {
-        match self.focus.insert(item) {
-            // We successfully inserted at the focus, so siblings don't need to be changed
-            Ok(focus) => Ok(Self::from_parts(self.siblings, focus)),
+    fn insert(
+        self,
+        item: Self::Item,
+        retention: Retention,
+    ) -> Result<Self, (Self::Item, Result<Self::Complete, Hash>)> {
+        match self.focus.insert(item, retention.clone()) {
+            // We successfully inserted at the focus, so siblings don't need to be changed, but the
+            // retention accumulated so far in this focus may need to grow to match
+            Ok(focus) => Ok(Self::from_parts(
+                self.siblings,
+                focus,
+                self.retention.max(retention),
+            )),
 
             // We couldn't insert at the focus because it was full, so we need to move our path
             // rightwards and insert into a newly created focus
-            Err((item, sibling)) => match self.siblings.push(sibling) {
+            Err((item, sibling)) => match self.siblings.push((self.retention, sibling)) {
                 // We had enough room to add another sibling, so we set our focus to a new focus
-                // containing only the item we couldn't previously insert
-                Ok(siblings) => Ok(Self::from_parts(siblings, Focus::singleton(item))),
+                // containing only the item we couldn't previously insert, whose retention is just
+                // that of the new item
+                Ok(siblings) => Ok(Self::from_parts(
+                    siblings,
+                    Focus::singleton(item),
+                    retention,
+                )),
 
                 // We didn't have enough room to add another sibling, so we return a complete node
                 // as a carry, to be propagated up above us and added to some ancestor segment's
@@ -142,9 +379,12 @@ where
                         // Implicitly, we only hash the children together when we're pruning them
                         // (because otherwise we would lose that informtion); if at least one child
                         // and its sibling hashes/subtrees is preserved in a `Complete` node, then
-                        // we defer calculating the node hash until looking up an authentication path
+                        // we defer calculating the node hash until looking up an authentication path.
+                        // The retention tagged onto each of `complete`'s children (carried over from
+                        // `siblings` above) is what lets this decide, per child, whether it can be
+                        // pruned to a `Hash` or must be kept intact to serve a future witness.
                         super::Complete::from_children_or_else_hash(complete).map(|node| {
-                            if let Some(hash) = self.hash.get() {
+                            if let Some(&hash) = self.hash.get() {
                                 // This is okay because `complete` is guaranteed to have the same elements in
                                 // the same order as `siblings + [focus]`:
                                 node.set_hash_unchecked(hash)
@@ -157,3 +397,474 @@ where
         }
     }
 }
+
+/// Shared body of [`Active::retire`](RetireFrontier) and [`Bridge::retire`]: both are just a
+/// frontier's `retention`/`focus`/`siblings` fields, and retiring one is retiring all three the
+/// same way.
+fn retire_frontier_fields<Focus>(
+    retention: &mut Retention,
+    focus: &mut Focus,
+    siblings: &mut Three<(Retention, Result<Focus::Complete, Hash>)>,
+    id: u64,
+) where
+    Focus: crate::Active + GetHash + RetireFrontier,
+    Focus::Complete: GetHash + Height + Retire + Clone,
+{
+    // Retiring never changes a slot's hash, so there's no memoized root hash to invalidate here.
+    retention.retire(id);
+    focus.retire(id);
+    *siblings = retire_each(std::mem::replace(siblings, Three::new()), id);
+}
+
+impl<Focus> RetireFrontier for Active<Focus>
+where
+    Focus: crate::Active + GetHash + RetireFrontier,
+    Focus::Complete: GetHash + Height + Retire + Clone,
+{
+    fn retire(&mut self, id: u64) {
+        retire_frontier_fields(&mut self.retention, &mut self.focus, &mut self.siblings, id);
+    }
+}
+
+/// An ordered list of sibling-hash triples, from the leaf being witnessed up to the root.
+///
+/// Each level of the tree hashes four children together via [`Hash::node`]; at each level the
+/// witness retains the three sibling hashes that are *not* on the path to the leaf, so a verifier
+/// can fold them back through [`Hash::node`] (together with the leaf's own hash) to recompute the
+/// root, proving the leaf's membership.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct AuthPath(Vec<[Hash; 3]>);
+
+impl AuthPath {
+    /// The sibling-hash triples of this path, ordered from the leaf up to the root.
+    pub(crate) fn siblings(&self) -> &[[Hash; 3]] {
+        &self.0
+    }
+
+    /// Extend this path one level further toward the root, appending the sibling triple for that
+    /// level. Called bottom-up, as a witness lookup unwinds back out through each ancestor.
+    pub(crate) fn push_toward_root(&mut self, triple: [Hash; 3]) {
+        self.0.push(triple);
+    }
+}
+
+/// Extraction of an authentication path for a previously-inserted item, by its index.
+///
+/// Implemented by both [`Active`] and its corresponding `Complete` node, so that a witness lookup
+/// can walk across the boundary between the still-growing frontier and the subtrees it has
+/// already completed.
+pub(crate) trait Witness {
+    /// Walk from the root to the leaf at `index`, returning the ordered sibling-hash triples that
+    /// prove its membership, or `None` if the path descends into a subtree that was pruned to a
+    /// bare [`Hash`] (i.e. no witness was retained for it) or `index` was never inserted.
+    fn witness(&self, index: u64) -> Option<AuthPath>;
+}
+
+impl<Focus> Witness for Active<Focus>
+where
+    Focus: crate::Active + GetHash + Witness,
+    Focus::Complete: GetHash + Witness,
+{
+    fn witness(&self, index: u64) -> Option<AuthPath> {
+        let padding = Hash::padding();
+
+        // Each of the (up to) four children at this level spans this many leaves, so dividing the
+        // global leaf index by it tells us which child the path descends into
+        let child_capacity: u64 = 1 << (2 * Focus::HEIGHT);
+        let slot = (index / child_capacity) as usize;
+        let child_index = index % child_capacity;
+
+        #[inline]
+        fn sibling_hash<T: GetHash>(result: &Result<T, Hash>) -> Hash {
+            result
+                .as_ref()
+                .map(|complete| complete.hash())
+                .unwrap_or_else(|hash| *hash)
+        }
+
+        #[inline]
+        fn sibling_witness<T: Witness>(result: &Result<T, Hash>, index: u64) -> Option<AuthPath> {
+            result.as_ref().ok()?.witness(index)
+        }
+
+        // The hash of each of this segment's (up to) four children, in the same `a, b, c, d` order
+        // used by `hash_active`, together with the witness of whichever one `slot` selects
+        let (all_hashes, descend): ([Hash; 4], Option<AuthPath>) = match self.siblings.elems() {
+            Elems::_0([]) => {
+                let a = self.focus.hash();
+                let descend = (slot == 0)
+                    .then(|| self.focus.witness(child_index))
+                    .flatten();
+                ([a, padding, padding, padding], descend)
+            }
+            Elems::_1([s0]) => {
+                let a = sibling_hash(&s0.1);
+                let b = self.focus.hash();
+                let descend = match slot {
+                    0 => sibling_witness(&s0.1, child_index),
+                    1 => self.focus.witness(child_index),
+                    _ => None,
+                };
+                ([a, b, padding, padding], descend)
+            }
+            Elems::_2([s0, s1]) => {
+                let a = sibling_hash(&s0.1);
+                let b = sibling_hash(&s1.1);
+                let c = self.focus.hash();
+                let descend = match slot {
+                    0 => sibling_witness(&s0.1, child_index),
+                    1 => sibling_witness(&s1.1, child_index),
+                    2 => self.focus.witness(child_index),
+                    _ => None,
+                };
+                ([a, b, c, padding], descend)
+            }
+            Elems::_3([s0, s1, s2]) => {
+                let a = sibling_hash(&s0.1);
+                let b = sibling_hash(&s1.1);
+                let c = sibling_hash(&s2.1);
+                let d = self.focus.hash();
+                let descend = match slot {
+                    0 => sibling_witness(&s0.1, child_index),
+                    1 => sibling_witness(&s1.1, child_index),
+                    2 => sibling_witness(&s2.1, child_index),
+                    3 => self.focus.witness(child_index),
+                    _ => None,
+                };
+                ([a, b, c, d], descend)
+            }
+        };
+
+        // The three hashes not on the path, in position order, are what this level contributes
+        let mut others = all_hashes
+            .into_iter()
+            .enumerate()
+            .filter(|(position, _)| *position != slot)
+            .map(|(_, hash)| hash);
+        let triple = [others.next()?, others.next()?, others.next()?];
+
+        descend.map(|mut path| {
+            path.push_toward_root(triple);
+            path
+        })
+    }
+}
+
+/// The frontier state captured by a [`Checkpointed::checkpoint`] call.
+///
+/// A bridge holds just the `siblings`/`focus` that make up an [`Active`] frontier, reconstructed
+/// with no replay of the insertions in between on [`rewind`](Checkpointed::rewind).
+///
+/// KNOWN SCOPE GAP, flagged here rather than shipped silently: `checkpoint` below clones
+/// `siblings`/`focus` in full, including whatever they retain (anything tagged
+/// [`Retention::Marked`] or [`Retention::Checkpoint`]), so this is an O(retained-size) clone per
+/// checkpoint, not the O(height) pointer-sized delta "bridge" implies. Fixing that for real means
+/// structural sharing -- e.g. wrapping `Focus::Complete` children in `Rc`/`Arc` so a bridge clones
+/// by bumping refcounts -- which touches every signature generic over `Child` in this file and
+/// `complete.rs`. Needs explicit sign-off before taking that on.
+#[derive(Debug, Clone)]
+struct Bridge<Focus: crate::Active> {
+    siblings: Three<(Retention, Result<Focus::Complete, Hash>)>,
+    focus: Focus,
+    retention: Retention,
+}
+
+impl<Focus> Bridge<Focus>
+where
+    Focus: crate::Active + GetHash + RetireFrontier,
+    Focus::Complete: GetHash + Height + Retire + Clone,
+{
+    /// See [`RetireFrontier`]: a bridge is its own independent clone of the frontier, so retiring
+    /// an id on the live `Active` doesn't reach the copies still queued for newer checkpoints --
+    /// each of those needs the same id retired from it directly.
+    fn retire(&mut self, id: u64) {
+        retire_frontier_fields(&mut self.retention, &mut self.focus, &mut self.siblings, id);
+    }
+}
+
+/// An [`Active`] frontier augmented with a bounded stack of restorable checkpoints.
+///
+/// Modeled on the bridge/checkpoint design of Zcash's `incrementalmerkletree`, this lets a caller
+/// record the frontier at a block boundary with [`checkpoint`](Checkpointed::checkpoint) and later
+/// undo every insertion since that point with [`rewind`](Checkpointed::rewind) -- which is how a
+/// wallet recovers from a chain reorg without rebuilding its commitment tree from scratch.
+#[derive(Debug, Clone)]
+pub(crate) struct Checkpointed<Focus: crate::Active> {
+    active: Active<Focus>,
+    checkpoints: VecDeque<(u64, Bridge<Focus>)>,
+    next_checkpoint_id: u64,
+    max_checkpoints: usize,
+}
+
+impl<Focus> Checkpointed<Focus>
+where
+    Focus: crate::Active + GetHash + RetireFrontier,
+    Focus::Complete: GetHash + Height + Retire + Clone,
+{
+    /// Wrap an existing frontier, retaining at most `max_checkpoints` restore points.
+    pub(crate) fn new(active: Active<Focus>, max_checkpoints: usize) -> Self {
+        Self {
+            active,
+            checkpoints: VecDeque::new(),
+            next_checkpoint_id: 0,
+            max_checkpoints,
+        }
+    }
+
+    /// The current frontier, as of the most recent `insert`/`rewind`.
+    pub(crate) fn active(&self) -> &Active<Focus> {
+        &self.active
+    }
+
+    /// The current frontier, mutably: used by callers driving `insert` against the wrapped tree.
+    pub(crate) fn active_mut(&mut self) -> &mut Active<Focus> {
+        &mut self.active
+    }
+
+    /// The id that the *next* call to [`checkpoint`](Checkpointed::checkpoint) will assign. Tag
+    /// the last item inserted before that call with `Retention::checkpoint(id)`, so its witness is
+    /// held only until this checkpoint is rewound past or evicted, rather than kept forever.
+    pub(crate) fn next_checkpoint_id(&self) -> u64 {
+        self.next_checkpoint_id
+    }
+
+    /// Record the current frontier as a restorable marker, returning its id.
+    ///
+    /// If this would exceed `max_checkpoints`, the oldest outstanding checkpoint is evicted and
+    /// its id is retired from the live frontier *and* from every bridge still queued for a newer
+    /// checkpoint (each is an independent clone that may carry the same now-stale tag): any
+    /// subtree that was retained only because this checkpoint's witness might still be needed is
+    /// collapsed to a bare `Hash`, the same way an ephemeral subtree is collapsed during `insert`.
+    /// Structure still needed by a newer checkpoint or a [`Retention::Marked`] leaf is left alone.
+    pub(crate) fn checkpoint(&mut self) -> u64 {
+        let id = self.next_checkpoint_id;
+        self.next_checkpoint_id += 1;
+
+        self.checkpoints.push_back((
+            id,
+            Bridge {
+                siblings: self.active.siblings.clone(),
+                focus: self.active.focus.clone(),
+                retention: self.active.retention.clone(),
+            },
+        ));
+
+        if self.checkpoints.len() > self.max_checkpoints {
+            if let Some((evicted_id, _bridge)) = self.checkpoints.pop_front() {
+                self.active.retire(evicted_id);
+                for (_, bridge) in self.checkpoints.iter_mut() {
+                    bridge.retire(evicted_id);
+                }
+            }
+        }
+
+        id
+    }
+
+    /// Pop the most recent checkpoint and restore the frontier to exactly the state it had when
+    /// that checkpoint was taken, discarding all leaves inserted since.
+    ///
+    /// Returns `false`, leaving the frontier untouched, if there is no checkpoint to rewind to.
+    /// Rewinding past this checkpoint means it can never be rewound to again, so its id is
+    /// immediately retired from the restored frontier along with it.
+    pub(crate) fn rewind(&mut self) -> bool {
+        match self.checkpoints.pop_back() {
+            Some((
+                id,
+                Bridge {
+                    siblings,
+                    focus,
+                    retention,
+                },
+            )) => {
+                self.active = Active::from_parts(siblings, focus, retention);
+                self.active.retire(id);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Active as _;
+
+    /// A minimal single-item leaf, standing in for the bottom of a real tree in these tests: it
+    /// has no structure of its own, so every trait impl below is trivial delegation to its lone
+    /// [`Hash`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Leaf(Hash);
+
+    impl Height for Leaf {
+        const HEIGHT: usize = 0;
+    }
+
+    impl GetHash for Leaf {
+        fn hash(&self) -> Hash {
+            self.0
+        }
+    }
+
+    impl Witness for Leaf {
+        fn witness(&self, index: u64) -> Option<AuthPath> {
+            (index == 0).then(|| AuthPath(Vec::new()))
+        }
+    }
+
+    impl Retire for Leaf {
+        fn retire(self, _id: u64) -> Result<Self, Hash> {
+            // A leaf has no internal checkpoint structure of its own to prune.
+            Ok(self)
+        }
+    }
+
+    impl RetireFrontier for Leaf {
+        fn retire(&mut self, _id: u64) {}
+    }
+
+    impl crate::Active for Leaf {
+        type Item = Hash;
+        type Complete = Leaf;
+
+        fn singleton(item: Self::Item) -> Self {
+            Leaf(item)
+        }
+
+        fn complete(self) -> Result<Self::Complete, Hash> {
+            Ok(self)
+        }
+
+        fn alter<T>(&mut self, f: impl FnOnce(&mut Self::Item) -> T) -> Option<T> {
+            Some(f(&mut self.0))
+        }
+
+        fn insert(
+            self,
+            item: Self::Item,
+            _retention: Retention,
+        ) -> Result<Self, (Self::Item, Result<Self::Complete, Hash>)> {
+            // A leaf's capacity is always exactly the one item it was built with.
+            Err((item, self.complete()))
+        }
+    }
+
+    /// A distinct, deterministic stand-in for a real commitment hash, built only from the two
+    /// constructors [`Hash`] actually exposes.
+    fn leaf_hash(n: u64) -> Hash {
+        let padding = Hash::padding();
+        Hash::node(n as usize, padding, padding, padding, padding)
+    }
+
+    /// Fold a witnessed leaf's hash back up through its (single-level, in these tests) auth path,
+    /// the same way a verifier would, and return the recomputed root.
+    fn fold(leaf: Hash, slot: usize, path: &AuthPath) -> Hash {
+        let siblings = path.siblings();
+        assert_eq!(
+            siblings.len(),
+            1,
+            "these tests only build single-level trees"
+        );
+        let triple = siblings[0];
+        let (a, b, c, d) = match slot {
+            0 => (leaf, triple[0], triple[1], triple[2]),
+            1 => (triple[0], leaf, triple[1], triple[2]),
+            2 => (triple[0], triple[1], leaf, triple[2]),
+            3 => (triple[0], triple[1], triple[2], leaf),
+            _ => unreachable!("slot is always in 0..4"),
+        };
+        Hash::node(Leaf::HEIGHT + 1, a, b, c, d)
+    }
+
+    fn filled_segment() -> Active<Leaf> {
+        let hashes = [leaf_hash(0), leaf_hash(1), leaf_hash(2), leaf_hash(3)];
+        let active = Active::<Leaf>::singleton(hashes[0]);
+        let active = active.insert(hashes[1], Retention::checkpoint(7)).unwrap();
+        let active = active.insert(hashes[2], Retention::Marked).unwrap();
+        active.insert(hashes[3], Retention::Ephemeral).unwrap()
+    }
+
+    #[test]
+    fn witness_round_trips_to_the_root() {
+        let hashes = [leaf_hash(0), leaf_hash(1), leaf_hash(2), leaf_hash(3)];
+        let active = filled_segment();
+        let root = active.hash();
+
+        for (slot, &leaf) in hashes.iter().enumerate() {
+            let path = active
+                .witness(slot as u64)
+                .expect("every inserted leaf has a witness");
+            assert_eq!(fold(leaf, slot, &path), root);
+        }
+    }
+
+    #[test]
+    fn witness_is_none_for_a_pruned_subtree() {
+        let active = filled_segment();
+
+        // Force this segment to complete by overflowing it with a fifth leaf.
+        let (_item, complete) = active
+            .insert(leaf_hash(4), Retention::checkpoint(7))
+            .expect_err("a fifth leaf overflows a 4-leaf segment");
+        let complete = complete.expect("the Marked leaf keeps this segment from collapsing");
+
+        // Evicting checkpoint 7 prunes the leaf retained only for it...
+        let complete = complete
+            .retire(7)
+            .expect("the still-Marked leaf keeps the segment retained");
+        assert!(complete.witness(1).is_none());
+
+        // ...but leaves the still-Marked leaf witnessable.
+        assert!(complete.witness(2).is_some());
+    }
+
+    /// Insert `item` into `checkpointed`'s frontier, panicking if this segment is already full.
+    ///
+    /// `Checkpointed::active_mut` hands out a `&mut Active<Focus>`, but `insert` consumes `self`
+    /// by value, so driving it from a `&mut` needs to clone out, insert, and write back.
+    fn insert(checkpointed: &mut Checkpointed<Leaf>, item: Hash, retention: Retention) {
+        checkpointed.active = checkpointed
+            .active()
+            .clone()
+            .insert(item, retention)
+            .expect("this segment has room for one more leaf");
+    }
+
+    #[test]
+    fn rewind_restores_the_pre_checkpoint_root() {
+        let hashes = [leaf_hash(0), leaf_hash(1), leaf_hash(2), leaf_hash(3)];
+        let mut checkpointed = Checkpointed::new(Active::<Leaf>::singleton(hashes[0]), 8);
+        insert(&mut checkpointed, hashes[1], Retention::Ephemeral);
+
+        let root_before = checkpointed.active().hash();
+        checkpointed.checkpoint();
+
+        insert(&mut checkpointed, hashes[2], Retention::Ephemeral);
+        insert(&mut checkpointed, hashes[3], Retention::Ephemeral);
+        assert_ne!(checkpointed.active().hash(), root_before);
+
+        assert!(checkpointed.rewind());
+        assert_eq!(checkpointed.active().hash(), root_before);
+    }
+
+    #[test]
+    fn evicting_a_checkpoint_retires_its_tag() {
+        let hashes = [leaf_hash(0), leaf_hash(1), leaf_hash(2)];
+        let mut checkpointed = Checkpointed::new(Active::<Leaf>::singleton(hashes[0]), 1);
+
+        // This leaf is witnessable only as long as the checkpoint taken right after it is
+        // outstanding.
+        let tag = checkpointed.next_checkpoint_id();
+        insert(&mut checkpointed, hashes[1], Retention::checkpoint(tag));
+        checkpointed.checkpoint();
+        assert!(checkpointed.active().witness(1).is_some());
+
+        // A second checkpoint exceeds `max_checkpoints == 1`, evicting the first and retiring its
+        // tag -- the leaf above should lose its witness as a result.
+        insert(&mut checkpointed, hashes[2], Retention::Ephemeral);
+        checkpointed.checkpoint();
+        assert!(checkpointed.active().witness(1).is_none());
+    }
+}
@@ -0,0 +1,268 @@
+use std::sync::OnceLock;
+
+use crate::{Elems, GetHash, Hash, Height, Three};
+
+use super::active::{
+    any_retained, any_retained_three, retire_each, retire_slot, AuthPath, Retention, Retire,
+    Witness,
+};
+
+/// The (up to four) children retained by a [`Complete`] node.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Children<Child> {
+    /// A fully-packed segment: exactly four children, produced when an [`Active`](super::Active)
+    /// segment's siblings overflowed.
+    Whole(Box<[(Retention, Result<Child, Hash>); 4]>),
+    /// Fewer than four children: the rightmost edge of a tree that was finalized (via
+    /// `complete`) before its segment filled up.
+    Partial(Three<(Retention, Result<Child, Hash>)>),
+}
+
+/// A completed, no-longer-growing segment of the tree, retained because at least one of its
+/// descendants carries a [`Retention`] that still requires a witness.
+///
+/// Unlike [`Active`](super::Active), nothing about a `Complete` node ever changes again, so its
+/// root hash is memoized the same way: lazily, in a [`OnceLock`], on first `hash()` call.
+#[derive(Debug, Clone)]
+pub(crate) struct Complete<Child> {
+    children: Children<Child>,
+    hash: OnceLock<Hash>,
+}
+
+impl<Child: PartialEq> PartialEq for Complete<Child> {
+    fn eq(&self, other: &Self) -> bool {
+        // The memoized hash is populated opportunistically (see `set_hash_unchecked`), so two
+        // structurally identical nodes can have different cache states; compare only `children`.
+        self.children == other.children
+    }
+}
+
+impl<Child: Eq> Eq for Complete<Child> {}
+
+/// The hash of a child slot, whether it's still retained (`Ok`) or was already pruned (`Err`).
+#[inline]
+fn hash_of<T: GetHash>(result: &Result<T, Hash>) -> Hash {
+    result
+        .as_ref()
+        .map(|complete| complete.hash())
+        .unwrap_or_else(|hash| *hash)
+}
+
+/// The witness of a child slot, or `None` if it was pruned to a bare `Hash` before reaching it.
+#[inline]
+fn witness_of<T: Witness>(result: &Result<T, Hash>, index: u64) -> Option<AuthPath> {
+    result.as_ref().ok()?.witness(index)
+}
+
+impl<Child: GetHash + Height> Complete<Child> {
+    fn new(children: Children<Child>) -> Self {
+        Self {
+            children,
+            hash: OnceLock::new(),
+        }
+    }
+
+    /// Build a [`Complete`] node from a fully-packed, four-element segment, or collapse it to a
+    /// bare [`Hash`] right now if nothing in it needs to be witnessed.
+    ///
+    /// This is the retention-driven decision the carry path in `Active::insert` relies on: an
+    /// `Ephemeral`-only segment is pruned immediately rather than retained pending some future
+    /// witness lookup that will never come.
+    pub(crate) fn from_children_or_else_hash(
+        children: [(Retention, Result<Child, Hash>); 4],
+    ) -> Result<Self, Hash> {
+        if any_retained(&children) {
+            Ok(Self::new(Children::Whole(Box::new(children))))
+        } else {
+            Err(hash_whole(&children))
+        }
+    }
+
+    /// Build a [`Complete`] node from the (possibly partial) remaining siblings and the final
+    /// completed focus of an [`Active`](super::Active) segment being finalized, or collapse it to
+    /// a bare [`Hash`] if nothing in it needs to be witnessed.
+    pub(crate) fn from_siblings_and_focus_or_else_hash(
+        siblings: Three<(Retention, Result<Child, Hash>)>,
+        focus_retention: Retention,
+        focus: Result<Child, Hash>,
+    ) -> Result<Self, Hash> {
+        match siblings.push((focus_retention, focus)) {
+            Ok(siblings) => {
+                if any_retained_three(&siblings) {
+                    Ok(Self::new(Children::Partial(siblings)))
+                } else {
+                    Err(hash_partial::<Child>(&siblings))
+                }
+            }
+            Err(children) => Self::from_children_or_else_hash(children),
+        }
+    }
+
+    /// Force the memoized root hash to `hash`, without checking that it is actually correct.
+    ///
+    /// Safe to call because each caller already computed this exact hash before this node
+    /// existed: the carry path in `Active::insert` from the same elements in the same order, and
+    /// `Retire::retire` from the node it rebuilds, whose children keep the same hashes in the same
+    /// order even where they are pruned to a bare `Hash`.
+    pub(crate) fn set_hash_unchecked(&self, hash: Hash) {
+        // If the cache is already populated (e.g. another thread raced us), leave it: both values
+        // are guaranteed equal, since the hash is a pure function of the (unchanging) contents.
+        let _ = self.hash.set(hash);
+    }
+}
+
+impl<Child: GetHash + Height + Retire + Clone> Retire for Complete<Child> {
+    fn retire(self, id: u64) -> Result<Self, Hash> {
+        // Retiring never changes a slot's hash, so a memoized root hash survives unchanged.
+        let memoized = self.hash.get().copied();
+
+        let retired = match self.children {
+            Children::Whole(children) => {
+                let [a, b, c, d] = *children;
+                Self::from_children_or_else_hash([
+                    retire_slot(a, id),
+                    retire_slot(b, id),
+                    retire_slot(c, id),
+                    retire_slot(d, id),
+                ])
+            }
+            Children::Partial(siblings) => {
+                let siblings = retire_each(siblings, id);
+                if any_retained_three(&siblings) {
+                    Ok(Self::new(Children::Partial(siblings)))
+                } else {
+                    Err(hash_partial::<Child>(&siblings))
+                }
+            }
+        };
+
+        if let (Ok(node), Some(hash)) = (&retired, memoized) {
+            node.set_hash_unchecked(hash);
+        }
+
+        retired
+    }
+}
+
+fn hash_whole<Child: GetHash + Height>(children: &[(Retention, Result<Child, Hash>); 4]) -> Hash {
+    let [a, b, c, d] = [
+        hash_of(&children[0].1),
+        hash_of(&children[1].1),
+        hash_of(&children[2].1),
+        hash_of(&children[3].1),
+    ];
+    Hash::node(Child::HEIGHT + 1, a, b, c, d)
+}
+
+fn hash_partial<Child: GetHash + Height>(
+    siblings: &Three<(Retention, Result<Child, Hash>)>,
+) -> Hash {
+    let padding = Hash::padding();
+
+    #[inline]
+    fn hashes_of_all<T: GetHash, const N: usize>(
+        full: [&(Retention, Result<T, Hash>); N],
+    ) -> [Hash; N] {
+        full.map(|(_retention, result)| hash_of(result))
+    }
+
+    let (a, b, c, d) = match siblings.elems() {
+        Elems::_0([]) => (padding, padding, padding, padding),
+        Elems::_1(full) => {
+            let [a] = hashes_of_all(full);
+            (a, padding, padding, padding)
+        }
+        Elems::_2(full) => {
+            let [a, b] = hashes_of_all(full);
+            (a, b, padding, padding)
+        }
+        Elems::_3(full) => {
+            let [a, b, c] = hashes_of_all(full);
+            (a, b, c, padding)
+        }
+    };
+
+    Hash::node(Child::HEIGHT + 1, a, b, c, d)
+}
+
+impl<Child: Height> Height for Complete<Child> {
+    const HEIGHT: usize = Child::HEIGHT + 1;
+}
+
+impl<Child: GetHash + Height> GetHash for Complete<Child> {
+    fn hash(&self) -> Hash {
+        *self.hash.get_or_init(|| match &self.children {
+            Children::Whole(children) => hash_whole(children),
+            Children::Partial(siblings) => hash_partial(siblings),
+        })
+    }
+}
+
+impl<Child: GetHash + Height + Witness> Witness for Complete<Child> {
+    fn witness(&self, index: u64) -> Option<AuthPath> {
+        let padding = Hash::padding();
+        let child_capacity: u64 = 1 << (2 * Child::HEIGHT);
+        let slot = (index / child_capacity) as usize;
+        let child_index = index % child_capacity;
+
+        let (all_hashes, descend): ([Hash; 4], Option<AuthPath>) = match &self.children {
+            Children::Whole(children) => {
+                let hashes = [
+                    hash_of(&children[0].1),
+                    hash_of(&children[1].1),
+                    hash_of(&children[2].1),
+                    hash_of(&children[3].1),
+                ];
+                let descend = children
+                    .get(slot)
+                    .and_then(|(_, result)| witness_of(result, child_index));
+                (hashes, descend)
+            }
+            Children::Partial(siblings) => match siblings.elems() {
+                Elems::_0([]) => ([padding; 4], None),
+                Elems::_1([s0]) => {
+                    let a = hash_of(&s0.1);
+                    let descend = match slot {
+                        0 => witness_of(&s0.1, child_index),
+                        _ => None,
+                    };
+                    ([a, padding, padding, padding], descend)
+                }
+                Elems::_2([s0, s1]) => {
+                    let a = hash_of(&s0.1);
+                    let b = hash_of(&s1.1);
+                    let descend = match slot {
+                        0 => witness_of(&s0.1, child_index),
+                        1 => witness_of(&s1.1, child_index),
+                        _ => None,
+                    };
+                    ([a, b, padding, padding], descend)
+                }
+                Elems::_3([s0, s1, s2]) => {
+                    let a = hash_of(&s0.1);
+                    let b = hash_of(&s1.1);
+                    let c = hash_of(&s2.1);
+                    let descend = match slot {
+                        0 => witness_of(&s0.1, child_index),
+                        1 => witness_of(&s1.1, child_index),
+                        2 => witness_of(&s2.1, child_index),
+                        _ => None,
+                    };
+                    ([a, b, c, padding], descend)
+                }
+            },
+        };
+
+        let mut others = all_hashes
+            .into_iter()
+            .enumerate()
+            .filter(|(position, _)| *position != slot)
+            .map(|(_, hash)| hash);
+        let triple = [others.next()?, others.next()?, others.next()?];
+
+        descend.map(|mut path| {
+            path.push_toward_root(triple);
+            path
+        })
+    }
+}